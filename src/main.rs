@@ -1,19 +1,38 @@
 use anyhow::bail;
 use std::{
+    collections::{HashMap, HashSet},
     fs::File,
     io::{self, BufWriter, Write},
     path::PathBuf,
 };
 
 use clap::Parser;
-use log::{LevelFilter, trace};
+use log::{LevelFilter, trace, warn};
 use rand::{
     Rng, SeedableRng,
-    distr::{Distribution, Uniform},
+    distr::{Distribution, Uniform, weighted::WeightedIndex},
     rngs::SmallRng,
     seq::IndexedRandom,
 };
 
+/// A segment visited in a particular orientation, as used when walking paths through the graph.
+type OrientedNode = (usize, char);
+
+/// Maps an oriented node to the oriented nodes reachable from it by an emitted link, so that
+/// paths can later be walked without re-deriving the edge set from the model that produced it.
+type Adjacency = HashMap<OrientedNode, Vec<OrientedNode>>;
+
+/// The topology used to draw edges between the generated nodes.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Model {
+    /// Uniformly random `from`/`to` pairs, producing an Erdős–Rényi-like graph.
+    Uniform,
+    /// A Watts–Strogatz small-world graph: a ring lattice with a fraction of edges rewired at random.
+    SmallWorld,
+    /// A Barabási–Albert scale-free graph grown by preferential attachment.
+    ScaleFree,
+}
+
 #[derive(Parser)]
 struct Cli {
     /// The file to write the generated GFA graph to.
@@ -34,6 +53,53 @@ struct Cli {
     #[clap(short = 'c', long)]
     ensure_strongly_connected: bool,
 
+    /// The topology used to draw edges between nodes.
+    #[clap(long, value_enum, default_value_t = Model::Uniform)]
+    model: Model,
+
+    /// The probability of rewiring a ring edge to a uniformly random target.
+    /// Only used with `--model small-world`.
+    #[clap(long, default_value_t = 0.1)]
+    rewire_prob: f64,
+
+    /// The number of ring neighbors each node is connected to on each side.
+    /// Only used with `--model small-world`.
+    #[clap(long, default_value_t = 2)]
+    neighbors: usize,
+
+    /// The number of links each new node attaches with. Only used with `--model scale-free`.
+    #[clap(short = 'm', long, default_value_t = 2)]
+    attach: usize,
+
+    /// If set, forbids self-loops and parallel edges. A link and its reverse complement (e.g.
+    /// `a+ -> b+` and `b- -> a-`) are treated as the same edge. Only used with `--model uniform`.
+    #[clap(long)]
+    simple: bool,
+
+    /// The number of random-walk paths to emit as `P` (and optionally `W`) lines.
+    /// If not provided, no paths are written.
+    #[clap(long)]
+    path_count: Option<usize>,
+
+    /// The length of each path, as an inclusive `min..max` range of visited segments.
+    #[clap(long, default_value = "5..15", value_parser = parse_usize_range)]
+    path_length: (usize, usize),
+
+    /// If set, also emit each path as a GFA 1.1 `W` line in addition to the `P` line.
+    #[clap(long)]
+    walk_format: bool,
+
+    /// The overlap length range (inclusive) sampled for each link's CIGAR, as `min..max`. The
+    /// sampled length is capped at the shorter of the two incident segments. If not provided,
+    /// every link uses a `0M` overlap as before.
+    #[clap(long, value_parser = parse_usize_range)]
+    overlap: Option<(usize, usize)>,
+
+    /// If set, some overlaps are emitted as an indel-containing CIGAR (e.g. `{a}M{b}I{c}M`)
+    /// instead of a single `{k}M` match. Only used with `--overlap`.
+    #[clap(long)]
+    overlap_variants: bool,
+
     /// The seed for the random number generator. If not provided, a random seed will be used.
     #[clap(short = 's', long)]
     seed: Option<u64>,
@@ -43,6 +109,72 @@ struct Cli {
     log_level: LevelFilter,
 }
 
+/// Parses a `min..max` command line argument into an inclusive bound pair.
+fn parse_usize_range(s: &str) -> Result<(usize, usize), String> {
+    let (min, max) = s
+        .split_once("..")
+        .ok_or_else(|| format!("expected a range in the form min..max, got '{s}'"))?;
+    let min: usize = min
+        .parse()
+        .map_err(|error| format!("invalid range start '{min}': {error}"))?;
+    let max: usize = max
+        .parse()
+        .map_err(|error| format!("invalid range end '{max}': {error}"))?;
+    if min > max {
+        return Err(format!("range start must be <= end, got '{s}'"));
+    }
+    Ok((min, max))
+}
+
+/// Bundles the parameters needed to pick a link's overlap CIGAR.
+struct OverlapConfig<'a> {
+    segment_lengths: &'a [usize],
+    range: Option<(usize, usize)>,
+    variants: bool,
+}
+
+impl OverlapConfig<'_> {
+    /// Samples a CIGAR for the link `from -> to`, bounded by the shorter of the two segments.
+    fn sample_cigar(&self, rng: &mut impl Rng, from: usize, to: usize) -> String {
+        let Some(range) = self.range else {
+            return "0M".to_string();
+        };
+
+        let max_overlap = range.1.min(self.segment_lengths[from].min(self.segment_lengths[to]));
+        let min_overlap = range.0.min(max_overlap);
+        let overlap = Uniform::new_inclusive(min_overlap, max_overlap)
+            .unwrap()
+            .sample(rng);
+
+        if self.variants && overlap >= 3 && rng.random_bool(0.3) {
+            let insert = Uniform::new_inclusive(1, 3.min(overlap - 2)).unwrap().sample(rng);
+            let matched = Uniform::new_inclusive(1, overlap - insert - 1).unwrap().sample(rng);
+            format!("{matched}M{insert}I{}M", overlap - insert - matched)
+        } else {
+            format!("{overlap}M")
+        }
+    }
+}
+
+/// Writes a single `L` line and records it in `adjacency` so it can later be traversed by a path walk.
+fn write_link(
+    output: &mut impl Write,
+    adjacency: &mut Adjacency,
+    overlap: &OverlapConfig,
+    rng: &mut impl Rng,
+    from: OrientedNode,
+    to: OrientedNode,
+) -> anyhow::Result<()> {
+    let cigar = overlap.sample_cigar(rng, from.0, to.0);
+    writeln!(
+        output,
+        "L\t{}\t{}\t{}\t{}\t{}",
+        from.0, from.1, to.0, to.1, cigar
+    )?;
+    adjacency.entry(from).or_default().push(to);
+    Ok(())
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     initialise_logger(cli.log_level);
@@ -51,6 +183,52 @@ fn main() -> anyhow::Result<()> {
         bail!("Cannot ensure strong connectivity with fewer edges than nodes.");
     }
 
+    match cli.model {
+        Model::Uniform => {}
+        Model::SmallWorld => {
+            if cli.neighbors >= cli.node_count {
+                bail!(
+                    "--model small-world requires --neighbors to be less than --node-count ({}), but --neighbors was {}.",
+                    cli.node_count,
+                    cli.neighbors
+                );
+            }
+
+            let expected_edge_count = cli.node_count * cli.neighbors;
+            if cli.edge_count != expected_edge_count {
+                bail!(
+                    "For --model small-world, --edge-count must equal node_count * neighbors ({expected_edge_count}), but was {}.",
+                    cli.edge_count
+                );
+            }
+        }
+        Model::ScaleFree => {
+            warn!(
+                "--edge-count does not set the number of edges with --model scale-free; the edge count is determined by node_count and --attach instead (~{} edges)."
+                , cli.node_count * cli.attach
+            );
+        }
+    }
+
+    if cli.simple && cli.model != Model::Uniform {
+        bail!("--simple is only used with --model uniform.");
+    }
+
+    if cli.simple {
+        let max_distinct_edges = 2 * cli.node_count * cli.node_count.saturating_sub(1);
+        if cli.edge_count > max_distinct_edges {
+            bail!(
+                "--simple allows at most {max_distinct_edges} distinct edges for {} nodes, but --edge-count was {}.",
+                cli.node_count,
+                cli.edge_count
+            );
+        }
+    }
+
+    if cli.overlap_variants && cli.overlap.is_none() {
+        bail!("--overlap-variants requires --overlap to be set.");
+    }
+
     let mut output = BufWriter::new(if &cli.output_file == "-" {
         Box::new(io::stdout()) as Box<dyn Write>
     } else {
@@ -62,18 +240,47 @@ fn main() -> anyhow::Result<()> {
     writeln!(output, "H\tVN:Z:1.0")?;
 
     trace!("Writing nodes");
-    for node_id in 1..=cli.node_count {
+    let mut segment_lengths = vec![0usize; cli.node_count + 1];
+    for (node_id, segment_length) in segment_lengths.iter_mut().enumerate().skip(1) {
         let sequence = random_dna_string(&mut rng, Uniform::new_inclusive(5, 15).unwrap());
+        *segment_length = sequence.chars().count();
         writeln!(output, "S\t{}\t{}", node_id, sequence)?;
     }
 
+    let overlap = OverlapConfig {
+        segment_lengths: &segment_lengths,
+        range: cli.overlap,
+        variants: cli.overlap_variants,
+    };
+
     trace!("Writing edges");
+    let mut adjacency: Adjacency = HashMap::new();
+    let mut emitted_edges: HashSet<(usize, char, usize, char)> = HashSet::new();
     let edge_count = if cli.ensure_strongly_connected {
         trace!("Ensuring strong connectivity by creating a cycle through all nodes");
         for i in 1..cli.node_count {
-            writeln!(output, "L\t{}\t+\t{}\t+\t0M", i, i + 1)?;
+            write_link(
+                &mut output,
+                &mut adjacency,
+                &overlap,
+                &mut rng,
+                (i, '+'),
+                (i + 1, '+'),
+            )?;
+        }
+        write_link(
+            &mut output,
+            &mut adjacency,
+            &overlap,
+            &mut rng,
+            (cli.node_count, '+'),
+            (1, '+'),
+        )?;
+        if cli.simple {
+            for i in 1..=cli.node_count {
+                emitted_edges.insert(canonicalize_edge(i, '+', i % cli.node_count + 1, '+'));
+            }
         }
-        writeln!(output, "L\t{}\t+\t1\t+\t0M", cli.node_count)?;
 
         trace!("Writing remaining edges");
         cli.edge_count - cli.node_count
@@ -81,23 +288,255 @@ fn main() -> anyhow::Result<()> {
         cli.edge_count
     };
 
-    let random_node = Uniform::new_inclusive(1, cli.node_count).unwrap();
-    let signs = ['+', '-'];
-    for _ in 0..edge_count {
-        let from = random_node.sample(&mut rng);
-        let to = random_node.sample(&mut rng);
-        let from_sign = signs.choose(&mut rng).unwrap();
-        let to_sign = signs.choose(&mut rng).unwrap();
-        writeln!(
-            output,
-            "L\t{}\t{}\t{}\t{}\t0M",
-            from, from_sign, to, to_sign
+    match cli.model {
+        Model::Uniform => {
+            let random_node = Uniform::new_inclusive(1, cli.node_count).unwrap();
+            let signs = ['+', '-'];
+            for _ in 0..edge_count {
+                loop {
+                    let from = random_node.sample(&mut rng);
+                    let to = random_node.sample(&mut rng);
+                    let from_sign = *signs.choose(&mut rng).unwrap();
+                    let to_sign = *signs.choose(&mut rng).unwrap();
+
+                    if cli.simple {
+                        if from == to {
+                            continue;
+                        }
+                        if !emitted_edges.insert(canonicalize_edge(from, from_sign, to, to_sign)) {
+                            continue;
+                        }
+                    }
+
+                    write_link(
+                        &mut output,
+                        &mut adjacency,
+                        &overlap,
+                        &mut rng,
+                        (from, from_sign),
+                        (to, to_sign),
+                    )?;
+                    break;
+                }
+            }
+        }
+        Model::SmallWorld => {
+            trace!("Writing small-world ring lattice with rewiring");
+            write_small_world_edges(
+                &mut output,
+                &mut adjacency,
+                &overlap,
+                &mut rng,
+                cli.node_count,
+                cli.neighbors,
+                cli.rewire_prob,
+            )?;
+        }
+        Model::ScaleFree => {
+            trace!("Writing scale-free graph by preferential attachment");
+            write_scale_free_edges(
+                &mut output,
+                &mut adjacency,
+                &overlap,
+                &mut rng,
+                cli.node_count,
+                cli.attach,
+            )?;
+        }
+    }
+
+    if let Some(path_count) = cli.path_count {
+        trace!("Writing paths");
+        write_paths(
+            &mut output,
+            &adjacency,
+            &mut rng,
+            cli.node_count,
+            path_count,
+            cli.path_length,
+            cli.walk_format,
         )?;
     }
 
     Ok(())
 }
 
+/// Lays `node_count` nodes out on a ring, connects each node to its `neighbors` nearest
+/// successors, and rewires each ring edge to a uniformly random target with probability `rewire_prob`.
+fn write_small_world_edges(
+    output: &mut impl Write,
+    adjacency: &mut Adjacency,
+    overlap: &OverlapConfig,
+    rng: &mut impl Rng,
+    node_count: usize,
+    neighbors: usize,
+    rewire_prob: f64,
+) -> anyhow::Result<()> {
+    let random_node = Uniform::new_inclusive(1, node_count).unwrap();
+    let signs = ['+', '-'];
+    let mut existing_targets: HashSet<(usize, usize)> = HashSet::new();
+
+    for i in 1..=node_count {
+        for j in 1..=neighbors {
+            let mut to = (i - 1 + j) % node_count + 1;
+
+            if rng.random_bool(rewire_prob) || existing_targets.contains(&(i, to)) {
+                trace!("Rewiring ring edge from node {i}");
+                loop {
+                    let candidate = random_node.sample(rng);
+                    if candidate != i && !existing_targets.contains(&(i, candidate)) {
+                        to = candidate;
+                        break;
+                    }
+                }
+            }
+
+            existing_targets.insert((i, to));
+            let from_sign = *signs.choose(rng).unwrap();
+            let to_sign = *signs.choose(rng).unwrap();
+            write_link(output, adjacency, overlap, rng, (i, from_sign), (to, to_sign))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Flips the orientation of a segment end, as used when reversing the traversal direction of a link.
+fn flip_sign(sign: char) -> char {
+    if sign == '+' { '-' } else { '+' }
+}
+
+/// Canonicalizes a link so that `a+ -> b+` and its reverse complement `b- -> a-` map to the same
+/// key, letting a `HashSet` deduplicate them as a single edge.
+fn canonicalize_edge(
+    from: usize,
+    from_sign: char,
+    to: usize,
+    to_sign: char,
+) -> (usize, char, usize, char) {
+    let forward = (from, from_sign, to, to_sign);
+    let reverse_complement = (to, flip_sign(to_sign), from, flip_sign(from_sign));
+    forward.min(reverse_complement)
+}
+
+/// Grows a Barabási–Albert scale-free graph: starts from a seed clique of `attach + 1` mutually
+/// connected nodes, then introduces the remaining nodes one at a time, each drawing `attach`
+/// distinct existing targets with probability proportional to their current degree.
+fn write_scale_free_edges(
+    output: &mut impl Write,
+    adjacency: &mut Adjacency,
+    overlap: &OverlapConfig,
+    rng: &mut impl Rng,
+    node_count: usize,
+    attach: usize,
+) -> anyhow::Result<()> {
+    if attach == 0 {
+        bail!("--attach must be at least 1.");
+    }
+    if node_count < attach + 1 {
+        bail!(
+            "--model scale-free requires at least attach + 1 ({}) nodes.",
+            attach + 1
+        );
+    }
+
+    let signs = ['+', '-'];
+    let mut degrees = vec![0usize; node_count + 1];
+
+    trace!("Writing scale-free seed clique of {} nodes", attach + 1);
+    for i in 1..=attach + 1 {
+        for j in (i + 1)..=attach + 1 {
+            let from_sign = *signs.choose(rng).unwrap();
+            let to_sign = *signs.choose(rng).unwrap();
+            write_link(output, adjacency, overlap, rng, (i, from_sign), (j, to_sign))?;
+            degrees[i] += 1;
+            degrees[j] += 1;
+        }
+    }
+
+    for new_node in (attach + 2)..=node_count {
+        let weights = WeightedIndex::new(&degrees[1..new_node]).unwrap();
+        let mut targets = HashSet::new();
+        while targets.len() < attach {
+            let target = weights.sample(rng) + 1;
+            if targets.insert(target) {
+                let from_sign = *signs.choose(rng).unwrap();
+                let to_sign = *signs.choose(rng).unwrap();
+                write_link(
+                    output,
+                    adjacency,
+                    overlap,
+                    rng,
+                    (new_node, from_sign),
+                    (target, to_sign),
+                )?;
+                degrees[target] += 1;
+            }
+        }
+        degrees[new_node] += attach;
+    }
+
+    Ok(())
+}
+
+/// Performs `path_count` random walks through the graph and writes each as a GFA1 `P` line
+/// (and, if `walk_format` is set, also as a GFA 1.1 `W` line), following only links that were
+/// actually emitted. A walk that reaches a dead end simply ends early.
+fn write_paths(
+    output: &mut impl Write,
+    adjacency: &Adjacency,
+    rng: &mut impl Rng,
+    node_count: usize,
+    path_count: usize,
+    path_length: (usize, usize),
+    walk_format: bool,
+) -> anyhow::Result<()> {
+    let (min_length, max_length) = path_length;
+    let length_distribution = Uniform::new_inclusive(min_length, max_length).unwrap();
+    let random_node = Uniform::new_inclusive(1, node_count).unwrap();
+    let signs = ['+', '-'];
+
+    for path_index in 1..=path_count {
+        let length = length_distribution.sample(rng);
+        let mut walk = vec![(random_node.sample(rng), *signs.choose(rng).unwrap())];
+
+        while walk.len() < length {
+            let current = *walk.last().unwrap();
+            let Some(targets) = adjacency.get(&current).filter(|targets| !targets.is_empty()) else {
+                break;
+            };
+            walk.push(*targets.choose(rng).unwrap());
+        }
+
+        let path_name = format!("path_{path_index}");
+        let segment_names = walk
+            .iter()
+            .map(|(node, sign)| format!("{node}{sign}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(output, "P\t{}\t{}\t*", path_name, segment_names)?;
+
+        if walk_format {
+            let walk_string = walk
+                .iter()
+                .map(|(node, sign)| {
+                    let marker = if *sign == '+' { '>' } else { '<' };
+                    format!("{marker}{node}")
+                })
+                .collect::<String>();
+            writeln!(
+                output,
+                "W\tsample\t0\t{}\t0\t{}\t{}",
+                path_name,
+                walk.len(),
+                walk_string
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 fn random_dna_string(rng: &mut impl Rng, length_distribution: Uniform<usize>) -> String {
     let length = length_distribution.sample(rng);
 